@@ -766,6 +766,7 @@ pub struct AdminData<R: Runner> {
     pub ifs_blocks: u8,
     pub efs_blocks: u16,
     pub variant: Variant,
+    pub temperature: Option<i8>,
     pub version: Version,
     pub version_string: &'static str,
 }
@@ -783,6 +784,7 @@ impl<R: Runner> AdminData<R> {
             ifs_blocks: u8::MAX,
             efs_blocks: u16::MAX,
             variant,
+            temperature: None,
             version,
             version_string,
         }
@@ -794,10 +796,11 @@ pub struct AdminStatus {
     ifs_blocks: u8,
     efs_blocks: u16,
     variant: Variant,
+    temperature: Option<i8>,
 }
 
 impl admin_app::StatusBytes for AdminStatus {
-    type Serialized = [u8; 5];
+    type Serialized = [u8; 6];
     fn set_random_error(&mut self, value: bool) {
         self.init_status.set(InitStatus::RNG_ERROR, value);
     }
@@ -805,14 +808,17 @@ impl admin_app::StatusBytes for AdminStatus {
         self.init_status.contains(InitStatus::RNG_ERROR)
     }
 
-    fn serialize(&self) -> [u8; 5] {
+    fn serialize(&self) -> [u8; 6] {
         let efs_blocks = self.efs_blocks.to_be_bytes();
+        // i8::MIN signals that the temperature is not available
+        let temperature = self.temperature.unwrap_or(i8::MIN).to_be_bytes();
         [
             self.init_status.bits(),
             self.ifs_blocks,
             efs_blocks[0],
             efs_blocks[1],
             self.variant.into(),
+            temperature[0],
         ]
     }
 }
@@ -824,6 +830,7 @@ impl<R: Runner> AdminData<R> {
             ifs_blocks: self.ifs_blocks,
             efs_blocks: self.efs_blocks,
             variant: self.variant,
+            temperature: self.temperature,
         }
     }
 }
@@ -1103,7 +1110,8 @@ impl<R: Runner> App<R> for ProvisionerApp<R> {
 
 #[cfg(test)]
 mod tests {
-    use super::{Config, FidoConfig, OpcardConfig};
+    use super::{AdminStatus, Config, FidoConfig, InitStatus, OpcardConfig, Variant};
+    use admin_app::StatusBytes as _;
     use cbor_smol::{cbor_serialize_bytes, Bytes};
 
     #[test]
@@ -1124,4 +1132,19 @@ mod tests {
         // LPC55, this could be increased to 128 if necessary.
         assert!(data.len() < 64, "{}: {}", data.len(), hex::encode(&data));
     }
+
+    #[test]
+    fn test_admin_status_serialize() {
+        let mut status = AdminStatus {
+            init_status: InitStatus::EXTERNAL_FLASH_ERROR,
+            ifs_blocks: 0x12,
+            efs_blocks: 0x3456,
+            variant: Variant::Nrf52,
+            temperature: Some(-5),
+        };
+        assert_eq!(status.serialize(), [0x04, 0x12, 0x34, 0x56, 0x02, 0xfb]);
+
+        status.temperature = None;
+        assert_eq!(status.serialize(), [0x04, 0x12, 0x34, 0x56, 0x02, 0x80]);
+    }
 }
@@ -17,13 +17,15 @@ use ref_swap::OptionRefSwap;
 use trussed::{interrupt::InterruptFlag, platform::Store as _};
 use usb_device::{
     bus::UsbBusAllocator,
-    device::{UsbDevice, UsbDeviceBuilder, UsbVidPid},
+    device::{UsbDevice, UsbDeviceBuilder, UsbDeviceState, UsbVidPid},
 };
 use usbd_ccid::Ccid;
 use usbd_ctaphid::CtapHid;
 use utils::Version;
 
-use crate::{soc::Soc, Apps, Board, Runner, RunnerPlatform, RunnerStore, Trussed, UserInterface};
+use crate::{
+    soc::Soc, ui, Apps, Board, Runner, RunnerPlatform, RunnerStore, Trussed, UserInterface,
+};
 
 #[cfg(not(feature = "no-delog"))]
 delog!(Delogger, 3 * 1024, 512, DelogFlusher);
@@ -69,6 +71,7 @@ impl<S: Soc> UsbClasses<S> {
         self.ctaphid.check_for_app_response();
         self.ccid.check_for_app_response();
         self.usbd.poll(&mut [&mut self.ccid, &mut self.ctaphid]);
+        ui::set_suspended(self.usbd.state() == UsbDeviceState::Suspend);
     }
 }
 
@@ -149,6 +152,7 @@ pub fn init_apps<B: Board>(
 ) -> Apps<B> {
     let mut admin = AdminData::new(*store, B::Soc::VARIANT, version, version_string);
     admin.init_status = init_status;
+    admin.temperature = soc.temperature();
     if !nfc_powered {
         if let Ok(ifs_blocks) = store.ifs().available_blocks() {
             if let Ok(ifs_blocks) = u8::try_from(ifs_blocks) {
@@ -179,7 +183,7 @@ pub fn init_apps<B: Board>(
 
     let runner = Runner {
         uuid: *soc.uuid(),
-        is_efs_available: !nfc_powered,
+        is_efs_available: !nfc_powered && store.mount_report().efs.is_available(),
         _marker: Default::default(),
     };
     let data = Data {
@@ -34,7 +34,7 @@ use trussed::{client::Syscall, Platform};
 
 use crate::{
     soc::{Soc, Uuid},
-    store::{RunnerStore, StoragePointers},
+    store::{MountOutcome, MountPolicies, RunnerStore, StoragePointers},
     ui::{buttons::UserPresence, rgb_led::RgbLed, UserInterface},
 };
 
@@ -60,18 +60,32 @@ pub trait Board: StoragePointers {
 
     const BOARD_NAME: &'static str;
     const HAS_NFC: bool;
+    const MOUNT_POLICIES: MountPolicies = MountPolicies::DEFAULT;
 
     fn prepare_ifs(ifs: &mut Self::InternalStorage) {
         let _ = ifs;
     }
 
+    /// Returns a RAM stand-in that is mounted instead of the external filesystem if it cannot be
+    /// mounted.  If this returns `None`, the device panics in that case.
+    fn efs_fallback() -> Option<Self::ExternalStorage> {
+        None
+    }
+
+    /// Tries to repair the internal filesystem if it cannot be mounted.
+    ///
+    /// This is only called if mounting the internal filesystem failed, never for a filesystem that
+    /// can be mounted but failed the health check, so implementations may overwrite it.
+    ///
+    /// Returns [`MountOutcome::Recovered`] if the data could be restored and
+    /// [`MountOutcome::Formatted`] if the filesystem had to be formatted.
     fn recover_ifs(
         ifs_storage: &mut Self::InternalStorage,
         ifs_alloc: &mut Allocation<Self::InternalStorage>,
         efs_storage: &mut Self::ExternalStorage,
-    ) -> LfsResult<()> {
+    ) -> LfsResult<MountOutcome> {
         let _ = (ifs_alloc, efs_storage);
-        Filesystem::format(ifs_storage)
+        Filesystem::format(ifs_storage).map(|()| MountOutcome::Formatted)
     }
 }
 
@@ -17,7 +17,7 @@ use nrf52840_pac::{FICR, GPIOTE, P0, P1, POWER, PWM0, PWM1, PWM2, SPIM3, TIMER1,
 use crate::{
     flash::ExtFlashStorage,
     soc::nrf52::{flash::FlashStorage, rtic_monotonic::RtcMonotonic, Nrf52},
-    store::impl_storage_pointers,
+    store::{impl_storage_pointers, MountOutcome},
     ui::UserInterface,
     Board,
 };
@@ -62,7 +62,7 @@ impl Board for NK3AM {
         ifs_storage: &mut Self::InternalStorage,
         ifs_alloc: &mut Allocation<Self::InternalStorage>,
         efs_storage: &mut Self::ExternalStorage,
-    ) -> LfsResult<()> {
+    ) -> LfsResult<MountOutcome> {
         error_now!("IFS (nrf42) mount-fail");
 
         // regular mount failed, try mounting "old" (pre-journaling) IFS
@@ -83,17 +83,17 @@ impl Board for NK3AM {
             // migration went fine => use its resulting IFS
             if let Ok(()) = mounted_ifs {
                 info_now!("migration ok, mounting IFS");
-                Ok(())
+                Ok(MountOutcome::Recovered)
             // migration failed => format IFS
             } else {
                 error_now!("failed migration, formatting IFS");
-                Filesystem::format(ifs_storage)
+                Filesystem::format(ifs_storage).map(|()| MountOutcome::Formatted)
             }
         } else {
             info_now!("recovering from journal");
             // IFS and old-IFS cannot be mounted, try to recover from journal
             ifs_storage.recover_from_journal();
-            Ok(())
+            Ok(MountOutcome::Recovered)
         }
     }
 }
@@ -74,6 +74,10 @@ impl Board for NK3xN {
 
     const BOARD_NAME: &'static str = "nk3xn";
     const HAS_NFC: bool = true;
+
+    fn efs_fallback() -> Option<Self::ExternalStorage> {
+        Some(OptionalStorage::default())
+    }
 }
 
 pub type InternalFlashStorage = InternalFilesystem;
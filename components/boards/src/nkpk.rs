@@ -9,7 +9,7 @@ use super::nk3am::{
 };
 use crate::{
     soc::nrf52::{flash::FlashStorage, Nrf52},
-    store::impl_storage_pointers,
+    store::{impl_storage_pointers, MountOutcome},
     Board,
 };
 
@@ -40,11 +40,11 @@ impl Board for NKPK {
         ifs_storage: &mut Self::InternalStorage,
         _ifs_alloc: &mut Allocation<Self::InternalStorage>,
         _efs_storage: &mut Self::ExternalStorage,
-    ) -> LfsResult<()> {
+    ) -> LfsResult<MountOutcome> {
         error_now!("IFS (nrf42) mount-fail");
         // IFS cannot be mounted, try to recover from journal
         ifs_storage.recover_from_journal();
-        Ok(())
+        Ok(MountOutcome::Recovered)
     }
 }
 
@@ -26,4 +26,9 @@ pub trait Soc: Reboot + 'static {
     const VARIANT: Variant;
 
     fn uuid(&self) -> &Uuid;
+
+    /// Measures the die temperature in °C if the SoC has a temperature sensor.
+    fn temperature(&self) -> Option<i8> {
+        None
+    }
 }
@@ -1,6 +1,7 @@
 use apps::Variant;
 use nrf52840_hal::{
     clocks::Clocks,
+    temp::Temp,
     usbd::{UsbPeripheral, Usbd},
 };
 use nrf52840_pac::{Interrupt, SCB};
@@ -30,6 +31,13 @@ impl Soc for Nrf52 {
     fn uuid(&self) -> &Uuid {
         &self.uuid
     }
+
+    fn temperature(&self) -> Option<i8> {
+        // TEMP is not used anywhere else
+        let pac = unsafe { nrf52840_pac::Peripherals::steal() };
+        let temperature: i32 = Temp::new(pac.TEMP).measure().to_num();
+        i8::try_from(temperature).ok()
+    }
 }
 
 impl apps::Reboot for Nrf52 {
@@ -11,6 +11,7 @@ use littlefs2::{
     driver::Storage as LfsStorage,
     fs::{Allocation, Filesystem},
     io::Result as LfsResult,
+    path,
 };
 use trussed::store::{Fs, Store};
 
@@ -130,7 +131,89 @@ macro_rules! impl_storage_pointers {
 )]
 pub(crate) use impl_storage_pointers;
 
+/// Determines how [`init_store`] treats a filesystem before mounting it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MountPolicy {
+    /// Format or recover the filesystem only if it cannot be mounted.  If it can be mounted but
+    /// fails the health check, it is mounted as-is and reported as [`MountOutcome::Unhealthy`].
+    FormatIfCorrupt,
+    /// Format the filesystem if it fails the health check.  If it cannot be mounted at all, it is
+    /// formatted or recovered as with [`MountPolicy::FormatIfCorrupt`].  [`Board::recover_ifs`] is
+    /// never called for a filesystem that can still be mounted.
+    FormatIfUnhealthy,
+    /// Always format the filesystem, discarding its previous content.
+    FormatAlways,
+    /// Never modify the filesystem and do not mount it if it fails the health check.
+    ///
+    /// The filesystem is then reported as [`MountOutcome::Failed`].  For the external filesystem,
+    /// the RAM stand-in returned by [`Board::efs_fallback`] is mounted instead and apps do not use
+    /// the external filesystem.  If the board has no fallback, or for the internal and volatile
+    /// filesystems, the device panics.
+    Fail,
+}
+
+/// The mount policies for the internal, external and volatile filesystems.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MountPolicies {
+    pub ifs: MountPolicy,
+    pub efs: MountPolicy,
+    pub vfs: MountPolicy,
+}
+
+impl MountPolicies {
+    pub const DEFAULT: Self = Self {
+        ifs: MountPolicy::FormatIfCorrupt,
+        efs: MountPolicy::FormatIfCorrupt,
+        vfs: MountPolicy::FormatAlways,
+    };
+}
+
+impl Default for MountPolicies {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// The result of mounting a single filesystem in [`init_store`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MountOutcome {
+    /// The filesystem passed the health check and was mounted as-is.
+    #[default]
+    Mounted,
+    /// The filesystem failed the health check but could be mounted, so it was mounted as-is.  This
+    /// is also reported as a flash error in the [`InitStatus`].
+    Unhealthy,
+    /// The filesystem has been formatted, either because it was corrupted or because of its
+    /// [`MountPolicy`].
+    Formatted,
+    /// The filesystem was corrupted and its data has been restored by [`Board::recover_ifs`].
+    Recovered,
+    /// The filesystem is a small RAM stand-in because the board does not have external flash or
+    /// because it could not be initialized.
+    Simulated,
+    /// The filesystem was corrupted and could not be repaired, or it failed the health check with
+    /// [`MountPolicy::Fail`].  The external filesystem is replaced with the RAM stand-in returned
+    /// by [`Board::efs_fallback`].
+    Failed,
+}
+
+impl MountOutcome {
+    /// Returns true if the filesystem is mounted on its actual storage and can be used by apps.
+    pub fn is_available(&self) -> bool {
+        !matches!(self, Self::Simulated | Self::Failed)
+    }
+}
+
+/// The outcome of mounting the internal, external and volatile filesystems.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct MountReport {
+    pub ifs: MountOutcome,
+    pub efs: MountOutcome,
+    pub vfs: MountOutcome,
+}
+
 pub struct RunnerStore<S> {
+    report: MountReport,
     _marker: PhantomData<*mut S>,
 }
 
@@ -139,6 +222,7 @@ impl<S: StoragePointers> RunnerStore<S> {
         ifs: &'static Filesystem<'static, S::InternalStorage>,
         efs: &'static Filesystem<'static, S::ExternalStorage>,
         vfs: &'static Filesystem<'static, VolatileStorage>,
+        report: MountReport,
     ) -> Self {
         unsafe {
             S::ifs_ptr().write(Fs::new(ifs));
@@ -147,6 +231,7 @@ impl<S: StoragePointers> RunnerStore<S> {
         }
 
         Self {
+            report,
             _marker: Default::default(),
         }
     }
@@ -155,6 +240,11 @@ impl<S: StoragePointers> RunnerStore<S> {
         static mut VFS: MaybeUninit<Fs<VolatileStorage>> = MaybeUninit::uninit();
         VFS.as_mut_ptr()
     }
+
+    /// Returns the outcome of mounting the filesystems in [`init_store`].
+    pub fn mount_report(&self) -> MountReport {
+        self.report
+    }
 }
 
 impl<S> Clone for RunnerStore<S> {
@@ -183,10 +273,15 @@ unsafe impl<S: StoragePointers> Store for RunnerStore<S> {
     }
 }
 
+/// Mounts the internal, external and volatile filesystems according to [`Board::MOUNT_POLICIES`].
+///
+/// If `walk_root_dir` is false, the health check only checks that the filesystems can be mounted.
+/// This keeps the mount time low if the device is powered by NFC.
 pub fn init_store<B: Board>(
     int_flash: B::InternalStorage,
     ext_flash: B::ExternalStorage,
     simulated_efs: bool,
+    walk_root_dir: bool,
     status: &mut InitStatus,
 ) -> RunnerStore<B> {
     static CLAIMED: AtomicBool = AtomicBool::new(false);
@@ -198,6 +293,8 @@ pub fn init_store<B: Board>(
     static mut VOLATILE_FS_ALLOC: Option<Allocation<VolatileStorage>> = None;
     static mut VOLATILE_FS: Option<Filesystem<VolatileStorage>> = None;
 
+    let mut report = MountReport::default();
+
     unsafe {
         let ifs_storage = B::ifs_storage().insert(int_flash);
         let ifs_alloc = B::ifs_alloc().insert(Filesystem::allocate());
@@ -206,31 +303,133 @@ pub fn init_store<B: Board>(
         let vfs_storage = VOLATILE_STORAGE.insert(VolatileStorage::new());
         let vfs_alloc = VOLATILE_FS_ALLOC.insert(Filesystem::allocate());
 
-        let ifs = match init_ifs::<B>(ifs_storage, ifs_alloc, efs_storage, status) {
+        let ifs = match init_ifs::<B>(
+            ifs_storage,
+            ifs_alloc,
+            efs_storage,
+            walk_root_dir,
+            status,
+            &mut report,
+        ) {
             Ok(ifs) => B::ifs().insert(ifs),
             Err(_e) => {
                 error!("IFS Mount Error {:?}", _e);
+                report.ifs = MountOutcome::Failed;
                 panic!("IFS");
             }
         };
 
-        let efs = match init_efs::<B>(efs_storage, efs_alloc, simulated_efs, status) {
-            Ok(efs) => B::efs().insert(efs),
+        let efs = match init_efs::<B>(
+            efs_storage,
+            efs_alloc,
+            simulated_efs,
+            walk_root_dir,
+            status,
+            &mut report,
+        ) {
+            Ok(efs) => efs,
             Err(_e) => {
                 error!("EFS Mount Error {:?}", _e);
-                panic!("EFS");
+                status.insert(InitStatus::EXTERNAL_FLASH_ERROR);
+                report.efs = MountOutcome::Failed;
+                let Some(fallback) = B::efs_fallback() else {
+                    panic!("EFS");
+                };
+                info_now!("mounting RAM fallback for EFS");
+                // the storage and allocation passed to init_efs are no longer used
+                let efs_storage = B::efs_storage().insert(fallback);
+                let efs_alloc = B::efs_alloc().insert(Filesystem::allocate());
+                match init_simulated_efs(efs_storage, efs_alloc, status) {
+                    Ok(efs) => efs,
+                    Err(_e) => {
+                        error!("EFS fallback Mount Error {:?}", _e);
+                        panic!("EFS");
+                    }
+                }
             }
         };
+        let efs = B::efs().insert(efs);
 
-        let vfs = match init_vfs(vfs_storage, vfs_alloc) {
+        let vfs = match init_vfs(vfs_storage, vfs_alloc, B::MOUNT_POLICIES.vfs, &mut report) {
             Ok(vfs) => VOLATILE_FS.insert(vfs),
             Err(_e) => {
                 error!("VFS Mount Error {:?}", _e);
+                report.vfs = MountOutcome::Failed;
                 panic!("VFS");
             }
         };
 
-        RunnerStore::new(ifs, efs, vfs)
+        info_now!("mount report: {:?}", report);
+
+        RunnerStore::new(ifs, efs, vfs, report)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Health {
+    Healthy,
+    /// The filesystem can be mounted, but its root directory cannot be read.
+    Unhealthy,
+    Unmountable,
+}
+
+/// Mounts the filesystem and, if `walk_root_dir` is set, reads all entries of its root directory.
+///
+/// In contrast to [`Filesystem::is_mountable`], walking the root directory also detects corrupted
+/// metadata that only shows up when traversing the directory entries.
+fn check_health<S: Storage>(storage: &mut S, walk_root_dir: bool) -> Health {
+    Filesystem::mount_and_then(storage, |fs| {
+        if !walk_root_dir {
+            return Ok(Health::Healthy);
+        }
+        let walk = fs.read_dir_and_then(path!("/"), |dir| {
+            for entry in dir {
+                entry?;
+            }
+            Ok(())
+        });
+        Ok(if walk.is_ok() {
+            Health::Healthy
+        } else {
+            Health::Unhealthy
+        })
+    })
+    .unwrap_or(Health::Unmountable)
+}
+
+/// What [`init_store`] does with a filesystem before mounting it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Action {
+    /// Mount the filesystem as-is and report the given outcome.
+    Mount(MountOutcome),
+    Format,
+    /// Format the filesystem because it failed the health check.
+    FormatUnhealthy,
+    /// Format or recover the filesystem because it cannot be mounted.
+    Repair,
+    /// Leave the filesystem unchanged and report it as failed.
+    Refuse,
+}
+
+fn action(policy: MountPolicy, health: Health) -> Action {
+    match (policy, health) {
+        (MountPolicy::FormatAlways, _) => Action::Format,
+        (_, Health::Healthy) => Action::Mount(MountOutcome::Mounted),
+        (MountPolicy::FormatIfCorrupt, Health::Unhealthy) => Action::Mount(MountOutcome::Unhealthy),
+        (MountPolicy::FormatIfUnhealthy, Health::Unhealthy) => Action::FormatUnhealthy,
+        (MountPolicy::Fail, _) => Action::Refuse,
+        (MountPolicy::FormatIfCorrupt | MountPolicy::FormatIfUnhealthy, Health::Unmountable) => {
+            Action::Repair
+        }
+    }
+}
+
+fn plan<S: Storage>(storage: &mut S, policy: MountPolicy, walk_root_dir: bool) -> Action {
+    if policy == MountPolicy::FormatAlways {
+        // no need to check a filesystem that is formatted anyway
+        Action::Format
+    } else {
+        action(policy, check_health(storage, walk_root_dir))
     }
 }
 
@@ -239,17 +438,44 @@ fn init_ifs<B: Board>(
     ifs_storage: &'static mut B::InternalStorage,
     ifs_alloc: &'static mut Allocation<B::InternalStorage>,
     efs_storage: &mut B::ExternalStorage,
+    walk_root_dir: bool,
     status: &mut InitStatus,
+    report: &mut MountReport,
 ) -> LfsResult<Filesystem<'static, B::InternalStorage>> {
-    if !Filesystem::is_mountable(ifs_storage) {
+    match plan(ifs_storage, B::MOUNT_POLICIES.ifs, walk_root_dir) {
+        Action::Mount(outcome) => {
+            if outcome == MountOutcome::Unhealthy {
+                error_now!("IFS health check failed, mounting anyway");
+                status.insert(InitStatus::INTERNAL_FLASH_ERROR);
+            }
+            report.ifs = outcome;
+        }
+        Action::Format => {
+            info_now!("IFS mount policy => formatting");
+            Filesystem::format(ifs_storage)?;
+            report.ifs = MountOutcome::Formatted;
+        }
+        Action::FormatUnhealthy => {
+            status.insert(InitStatus::INTERNAL_FLASH_ERROR);
+            error_now!("IFS health check failed, formatting");
+            Filesystem::format(ifs_storage)?;
+            report.ifs = MountOutcome::Formatted;
+        }
+        Action::Refuse => {
+            status.insert(InitStatus::INTERNAL_FLASH_ERROR);
+            error_now!("IFS mount-fail, not mounting");
+            return Err(littlefs2::io::Error::Corruption);
+        }
         // handle provisioner
-        if cfg!(feature = "provisioner") {
+        Action::Repair if cfg!(feature = "provisioner") => {
             info_now!("IFS mount failed - provisioner => formatting");
-            Filesystem::format(ifs_storage).ok();
-        } else {
+            Filesystem::format(ifs_storage)?;
+            report.ifs = MountOutcome::Formatted;
+        }
+        Action::Repair => {
             status.insert(InitStatus::INTERNAL_FLASH_ERROR);
             error_now!("IFS mount-fail");
-            B::recover_ifs(ifs_storage, ifs_alloc, efs_storage).ok();
+            report.ifs = B::recover_ifs(ifs_storage, ifs_alloc, efs_storage)?;
         }
     }
 
@@ -258,22 +484,65 @@ fn init_ifs<B: Board>(
     Filesystem::mount(ifs_alloc, ifs_storage)
 }
 
+/// Mounts the external filesystem.  If this returns an error, [`init_store`] mounts the fallback
+/// from [`Board::efs_fallback`] instead.
 #[inline(always)]
 fn init_efs<B: Board>(
     efs_storage: &'static mut B::ExternalStorage,
     efs_alloc: &'static mut Allocation<B::ExternalStorage>,
     simulated_efs: bool,
+    walk_root_dir: bool,
     status: &mut InitStatus,
+    report: &mut MountReport,
 ) -> LfsResult<Filesystem<'static, B::ExternalStorage>> {
+    if simulated_efs {
+        report.efs = MountOutcome::Simulated;
+        return init_simulated_efs(efs_storage, efs_alloc, status);
+    }
+
+    match plan(efs_storage, B::MOUNT_POLICIES.efs, walk_root_dir) {
+        Action::Mount(outcome) => {
+            if outcome == MountOutcome::Unhealthy {
+                error_now!("EFS health check failed, mounting anyway");
+                status.insert(InitStatus::EXTERNAL_FLASH_ERROR);
+            }
+            report.efs = outcome;
+        }
+        Action::Format => {
+            info_now!("EFS mount policy => formatting");
+            Filesystem::format(efs_storage)?;
+            report.efs = MountOutcome::Formatted;
+        }
+        Action::Refuse => {
+            error_now!("EFS Mount Error, not mounting");
+            return Err(littlefs2::io::Error::Corruption);
+        }
+        Action::FormatUnhealthy | Action::Repair => {
+            let fmt_ext = Filesystem::format(efs_storage);
+            error_now!("EFS Mount Error, Reformat {:?}", fmt_ext);
+            status.insert(InitStatus::EXTERNAL_FLASH_ERROR);
+            fmt_ext?;
+            report.efs = MountOutcome::Formatted;
+        }
+    }
+    Filesystem::mount(efs_alloc, efs_storage)
+}
+
+#[inline(always)]
+fn init_simulated_efs<S: Storage>(
+    efs_storage: &'static mut S,
+    efs_alloc: &'static mut Allocation<S>,
+    status: &mut InitStatus,
+) -> LfsResult<Filesystem<'static, S>> {
     if !Filesystem::is_mountable(efs_storage) {
         let fmt_ext = Filesystem::format(efs_storage);
-        if simulated_efs && fmt_ext == Err(littlefs2::io::Error::NoSpace) {
+        if fmt_ext == Err(littlefs2::io::Error::NoSpace) {
             info_now!("Formatting simulated EFS failed as expected");
         } else {
             error_now!("EFS Mount Error, Reformat {:?}", fmt_ext);
             status.insert(InitStatus::EXTERNAL_FLASH_ERROR);
         }
-    };
+    }
     Filesystem::mount(efs_alloc, efs_storage)
 }
 
@@ -281,9 +550,151 @@ fn init_efs<B: Board>(
 fn init_vfs(
     vfs_storage: &'static mut VolatileStorage,
     vfs_alloc: &'static mut Allocation<VolatileStorage>,
+    policy: MountPolicy,
+    report: &mut MountReport,
 ) -> LfsResult<Filesystem<'static, VolatileStorage>> {
-    if !Filesystem::is_mountable(vfs_storage) {
-        Filesystem::format(vfs_storage).ok();
-    }
+    report.vfs = match plan(vfs_storage, policy, false) {
+        Action::Mount(outcome) => outcome,
+        Action::Format | Action::FormatUnhealthy | Action::Repair => {
+            Filesystem::format(vfs_storage)?;
+            MountOutcome::Formatted
+        }
+        Action::Refuse => return Err(littlefs2::io::Error::Corruption),
+    };
     Filesystem::mount(vfs_alloc, vfs_storage)
 }
+
+#[cfg(test)]
+mod tests {
+    use littlefs2::{fs::Filesystem, io::Error, path};
+    use utils::{FaultOperation, FaultyStorage, RamStorage};
+
+    use super::{
+        action, check_health, init_simulated_efs, plan, Action, Health, InitStatus, MountOutcome,
+        MountPolicy, VolatileStorage,
+    };
+
+    type TestStorage = FaultyStorage<VolatileStorage>;
+
+    fn erased() -> TestStorage {
+        FaultyStorage::new(VolatileStorage::new())
+    }
+
+    fn formatted() -> TestStorage {
+        let mut storage = erased();
+        Filesystem::format(&mut storage).unwrap();
+        Filesystem::mount_and_then(&mut storage, |fs| fs.write(path!("test"), b"data")).unwrap();
+        storage
+    }
+
+    #[test]
+    fn test_check_health() {
+        let mut storage = formatted();
+        assert_eq!(check_health(&mut storage, true), Health::Healthy);
+        assert_eq!(check_health(&mut storage, false), Health::Healthy);
+
+        storage.fail_next(FaultOperation::Read, Error::Io);
+        assert_eq!(check_health(&mut storage, true), Health::Unmountable);
+        assert_eq!(check_health(&mut storage, true), Health::Healthy);
+
+        assert_eq!(check_health(&mut erased(), true), Health::Unmountable);
+        assert_eq!(check_health(&mut erased(), false), Health::Unmountable);
+    }
+
+    #[test]
+    fn test_action() {
+        let mounted = Action::Mount(MountOutcome::Mounted);
+        let unhealthy = Action::Mount(MountOutcome::Unhealthy);
+        let cases = [
+            (MountPolicy::FormatIfCorrupt, Health::Healthy, mounted),
+            (MountPolicy::FormatIfCorrupt, Health::Unhealthy, unhealthy),
+            (
+                MountPolicy::FormatIfCorrupt,
+                Health::Unmountable,
+                Action::Repair,
+            ),
+            (MountPolicy::FormatIfUnhealthy, Health::Healthy, mounted),
+            (
+                MountPolicy::FormatIfUnhealthy,
+                Health::Unhealthy,
+                Action::FormatUnhealthy,
+            ),
+            (
+                MountPolicy::FormatIfUnhealthy,
+                Health::Unmountable,
+                Action::Repair,
+            ),
+            (MountPolicy::FormatAlways, Health::Healthy, Action::Format),
+            (MountPolicy::FormatAlways, Health::Unhealthy, Action::Format),
+            (
+                MountPolicy::FormatAlways,
+                Health::Unmountable,
+                Action::Format,
+            ),
+            (MountPolicy::Fail, Health::Healthy, mounted),
+            (MountPolicy::Fail, Health::Unhealthy, Action::Refuse),
+            (MountPolicy::Fail, Health::Unmountable, Action::Refuse),
+        ];
+        for (policy, health, expected) in cases {
+            assert_eq!(action(policy, health), expected, "{policy:?}, {health:?}");
+        }
+    }
+
+    #[test]
+    fn test_plan() {
+        let mut storage = formatted();
+        assert_eq!(
+            plan(&mut storage, MountPolicy::Fail, true),
+            Action::Mount(MountOutcome::Mounted)
+        );
+
+        // a read error while mounting makes the filesystem unmountable
+        storage.fail_next(FaultOperation::Read, Error::Io);
+        assert_eq!(
+            plan(&mut storage, MountPolicy::FormatIfCorrupt, true),
+            Action::Repair
+        );
+        storage.fail_next(FaultOperation::Read, Error::Io);
+        assert_eq!(plan(&mut storage, MountPolicy::Fail, true), Action::Refuse);
+
+        // FormatAlways does not read the storage
+        storage.fail_next(FaultOperation::Read, Error::Io);
+        assert_eq!(
+            plan(&mut storage, MountPolicy::FormatAlways, true),
+            Action::Format
+        );
+        assert_eq!(check_health(&mut storage, true), Health::Unmountable);
+
+        assert_eq!(
+            plan(&mut erased(), MountPolicy::FormatIfCorrupt, true),
+            Action::Repair
+        );
+        assert_eq!(plan(&mut erased(), MountPolicy::Fail, true), Action::Refuse);
+    }
+
+    #[test]
+    fn test_power_cut_during_format() {
+        let mut storage = formatted();
+        storage.cut_power_after(0);
+        assert_eq!(Filesystem::format(&mut storage), Err(Error::Io));
+        storage.restore_power();
+
+        assert_eq!(check_health(&mut storage, true), Health::Healthy);
+        let data =
+            Filesystem::mount_and_then(&mut storage, |fs| fs.read::<16>(path!("test"))).unwrap();
+        assert_eq!(&data[..], b"data");
+    }
+
+    #[test]
+    fn test_simulated_efs() {
+        extern crate std;
+        use std::boxed::Box;
+
+        // like the RAM fallback, the storage is too small for formatting but can be mounted
+        let storage = Box::leak(Box::new(RamStorage::<VolatileStorage, 256>::default()));
+        let alloc = Box::leak(Box::new(Filesystem::allocate()));
+        let mut status = InitStatus::empty();
+        assert!(init_simulated_efs(storage, alloc, &mut status).is_ok());
+        assert!(status.is_empty());
+    }
+}
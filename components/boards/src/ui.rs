@@ -34,6 +34,7 @@ const WHITE: Intensities = Intensities {
 };
 
 static WAITING: AtomicBool = AtomicBool::new(false);
+static SUSPENDED: AtomicBool = AtomicBool::new(false);
 
 fn set_waiting(waiting: bool) {
     WAITING.store(waiting, Relaxed);
@@ -43,6 +44,17 @@ pub fn is_waiting() -> bool {
     WAITING.load(Relaxed)
 }
 
+/// Marks the USB device as suspended by the host.
+///
+/// While suspended, the LED is turned off to stay within the USB suspend current limit.
+pub fn set_suspended(suspended: bool) {
+    SUSPENDED.store(suspended, Relaxed);
+}
+
+fn is_suspended() -> bool {
+    SUSPENDED.load(Relaxed)
+}
+
 pub trait Clock {
     fn uptime(&mut self) -> Duration;
 }
@@ -80,8 +92,12 @@ impl<C: Clock, P: UserPresence, L: RgbLed> UserInterface<C, P, L> {
     fn refresh_ui(&mut self, uptime: Duration) {
         if let Some(rgb) = &mut self.rgb {
             self.status.refresh(uptime);
-            let mode = self.status.led_mode(self.provisioner);
-            rgb.set(mode.color(uptime));
+            if is_suspended() {
+                rgb.turn_off();
+            } else {
+                let mode = self.status.led_mode(self.provisioner);
+                rgb.set(mode.color(uptime));
+            }
         }
     }
 }
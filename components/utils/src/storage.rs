@@ -126,3 +126,174 @@ impl<S, const RAM_SIZE: usize> From<S> for OptionalStorage<S, RAM_SIZE> {
         Self::Storage(storage)
     }
 }
+
+/// A storage operation that can fail in a [`FaultyStorage`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FaultOperation {
+    Read,
+    Write,
+    Erase,
+}
+
+/// A storage wrapper that injects faults into the wrapped storage, for testing error and recovery
+/// paths of the code using the filesystem.
+pub struct FaultyStorage<S> {
+    storage: S,
+    fault: Option<(FaultOperation, Error)>,
+    write_budget: Option<usize>,
+}
+
+impl<S: Storage> FaultyStorage<S> {
+    /// Wraps the given storage without injecting any faults.
+    pub fn new(storage: S) -> Self {
+        Self {
+            storage,
+            fault: None,
+            write_budget: None,
+        }
+    }
+
+    /// Let the next call of the given operation fail with the given error.
+    pub fn fail_next(&mut self, operation: FaultOperation, error: Error) {
+        self.fault = Some((operation, error));
+    }
+
+    /// Simulate a power cut after the given number of writes.  All further writes and erases fail
+    /// with [`Error::Io`][] and are not applied until [`restore_power`][Self::restore_power] is
+    /// called.
+    ///
+    /// The write that hits the power cut is rejected completely.  This does not simulate torn
+    /// writes where only a part of the data reaches the storage.
+    pub fn cut_power_after(&mut self, writes: usize) {
+        self.write_budget = Some(writes);
+    }
+
+    /// Ends a power cut started with [`cut_power_after`][Self::cut_power_after].
+    pub fn restore_power(&mut self) {
+        self.write_budget = None;
+    }
+
+    /// Returns false if writes and erases currently fail because of a simulated power cut.
+    pub fn is_powered(&self) -> bool {
+        self.write_budget != Some(0)
+    }
+
+    /// Access the wrapped storage, e. g. to corrupt its contents.
+    pub fn inner_mut(&mut self) -> &mut S {
+        &mut self.storage
+    }
+
+    /// Returns the wrapped storage.
+    pub fn into_inner(self) -> S {
+        self.storage
+    }
+
+    fn check(&mut self, operation: FaultOperation) -> Result<(), Error> {
+        if self.fault.as_ref().map(|(op, _)| *op) == Some(operation) {
+            if let Some((_, error)) = self.fault.take() {
+                return Err(error);
+            }
+        }
+        if operation != FaultOperation::Read {
+            match &mut self.write_budget {
+                Some(0) => return Err(Error::Io),
+                Some(budget) if operation == FaultOperation::Write => *budget -= 1,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<S: Storage> Storage for FaultyStorage<S> {
+    const BLOCK_SIZE: usize = S::BLOCK_SIZE;
+    const READ_SIZE: usize = S::READ_SIZE;
+    const WRITE_SIZE: usize = S::WRITE_SIZE;
+    const BLOCK_COUNT: usize = S::BLOCK_COUNT;
+
+    type CACHE_SIZE = S::CACHE_SIZE;
+    type LOOKAHEAD_SIZE = S::LOOKAHEAD_SIZE;
+
+    fn read(&mut self, off: usize, buf: &mut [u8]) -> Result<usize, Error> {
+        self.check(FaultOperation::Read)?;
+        self.storage.read(off, buf)
+    }
+
+    fn write(&mut self, off: usize, data: &[u8]) -> Result<usize, Error> {
+        self.check(FaultOperation::Write)?;
+        self.storage.write(off, data)
+    }
+
+    fn erase(&mut self, off: usize, len: usize) -> Result<usize, Error> {
+        self.check(FaultOperation::Erase)?;
+        self.storage.erase(off, len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use littlefs2::{consts, fs::Filesystem, path};
+
+    struct Geometry;
+
+    impl Storage for Geometry {
+        const BLOCK_SIZE: usize = 256;
+        const READ_SIZE: usize = 4;
+        const WRITE_SIZE: usize = 4;
+        const BLOCK_COUNT: usize = 16;
+
+        type CACHE_SIZE = consts::U256;
+        type LOOKAHEAD_SIZE = consts::U1;
+
+        fn read(&mut self, _off: usize, _buf: &mut [u8]) -> Result<usize, Error> {
+            unreachable!();
+        }
+
+        fn write(&mut self, _off: usize, _data: &[u8]) -> Result<usize, Error> {
+            unreachable!();
+        }
+
+        fn erase(&mut self, _off: usize, _len: usize) -> Result<usize, Error> {
+            unreachable!();
+        }
+    }
+
+    type TestStorage = FaultyStorage<RamStorage<Geometry, 4096>>;
+
+    fn formatted() -> TestStorage {
+        let mut storage = FaultyStorage::new(RamStorage::default());
+        Filesystem::format(&mut storage).unwrap();
+        storage
+    }
+
+    #[test]
+    fn test_fail_next() {
+        let mut storage = formatted();
+        // littlefs skips blocks that report Corruption, so inject an IO error instead
+        storage.fail_next(FaultOperation::Read, Error::Io);
+        assert!(!Filesystem::is_mountable(&mut storage));
+        assert!(Filesystem::is_mountable(&mut storage));
+
+        storage.fail_next(FaultOperation::Write, Error::Io);
+        assert_eq!(Filesystem::format(&mut storage), Err(Error::Io));
+        Filesystem::format(&mut storage).unwrap();
+    }
+
+    #[test]
+    fn test_power_cut() {
+        let mut storage = formatted();
+        Filesystem::mount_and_then(&mut storage, |fs| fs.write(path!("test"), b"old")).unwrap();
+
+        storage.cut_power_after(0);
+        let result = Filesystem::mount_and_then(&mut storage, |fs| fs.write(path!("test"), b"new"));
+        assert_eq!(result, Err(Error::Io));
+        assert!(!storage.is_powered());
+
+        storage.restore_power();
+        let data =
+            Filesystem::mount_and_then(&mut storage, |fs| fs.read::<16>(path!("test"))).unwrap();
+        assert_eq!(&data[..], b"old");
+    }
+}
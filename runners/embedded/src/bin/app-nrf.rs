@@ -67,7 +67,13 @@ mod app {
             board_gpio.flashnfc_spi.take().unwrap(),
             board_gpio.flash_cs.take().unwrap(),
         );
-        let store = store::init_store(internal_flash, external_flash, false, &mut init_status);
+        let store = store::init_store(
+            internal_flash,
+            external_flash,
+            false,
+            true,
+            &mut init_status,
+        );
 
         static NFC_CHANNEL: CcidChannel = Channel::new();
         let (_nfc_rq, nfc_rp) = NFC_CHANNEL.split().unwrap();
@@ -606,7 +606,15 @@ impl Stage4 {
         );
         // TODO: poll iso14443
         let simulated_efs = external.is_ram();
-        let store = store::init_store(internal, external, simulated_efs, &mut self.status);
+        // skip walking the root directories if NFC-powered to keep the mount time low
+        let walk_root_dir = !self.clocks.is_nfc_passive;
+        let store = store::init_store(
+            internal,
+            external,
+            simulated_efs,
+            walk_root_dir,
+            &mut self.status,
+        );
         info!("mount end {} ms", self.basic.perf_timer.elapsed().0 / 1000);
 
         // return to slow freq
@@ -62,8 +62,13 @@ mod app {
 
         let internal_flash = InternalFlashStorage::new(ctx.device.NVMC);
         let external_flash = ExternalFlashStorage::default();
-        let store =
-            store::init_store::<Board>(internal_flash, external_flash, true, &mut init_status);
+        let store = store::init_store::<Board>(
+            internal_flash,
+            external_flash,
+            true,
+            true,
+            &mut init_status,
+        );
 
         const USB_PRODUCT: &str = "Nitrokey Passkey";
         const USB_PRODUCT_ID: u16 = 0x42F3;